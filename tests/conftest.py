@@ -0,0 +1,132 @@
+import importlib
+import os
+import re
+import subprocess
+import sys
+import types
+from pathlib import Path
+
+import pytest
+
+script_path = Path(__file__).resolve().parent.parent / "mod-manager"
+
+# Records the calls instead of mounting, the actions in $STUB_HELPER_FAIL fail like the real ones would
+stub_helper = """#!/bin/sh
+echo "$@" >> "$STUB_HELPER_LOG"
+for action in $STUB_HELPER_FAIL; do
+    if [ "$action" = "$1" ]; then
+        echo "$1 failed" >&2
+        exit 32
+    fi
+done
+"""
+
+
+class ModManager():
+    def __init__(self, root, helper=stub_helper):
+        self.root = root
+        self.config_path = root / "config" / "mod-manager"
+        self.mod_root_path = root / "mods"
+        self.helper_log = root / "helper.log"
+
+        bin_path = root / "bin"
+        for directory in [self.config_path, self.mod_root_path, bin_path,
+                          root / "data", root / "cache", root / "runtime", root / "home"]:
+            directory.mkdir(parents=True, exist_ok=True)
+
+        helper_path = bin_path / "mod-manager-overlayfs-helper"
+        helper_path.write_text(helper)
+        helper_path.chmod(0o755)
+
+        self.env = dict(os.environ,
+                        HOME=str(root / "home"),
+                        XDG_CONFIG_HOME=str(root / "config"),
+                        XDG_DATA_HOME=str(root / "data"),
+                        XDG_CACHE_HOME=str(root / "cache"),
+                        XDG_RUNTIME_DIR=str(root / "runtime"),
+                        PATH=f"{bin_path}{os.pathsep}{os.environ['PATH']}",
+                        STUB_HELPER_LOG=str(self.helper_log),
+                        STUB_HELPER_FAIL="")
+        self.env.pop("MOD_MANAGER_SET", None)
+
+    def tree(self, path, files):
+        # Relative path -> content, None for an empty folder
+        for relative_path, content in files.items():
+            target = path / relative_path
+            if content is None:
+                target.mkdir(parents=True, exist_ok=True)
+                continue
+
+            target.parent.mkdir(parents=True, exist_ok=True)
+            target.write_text(content)
+
+        return path
+
+    def mod(self, name, files):
+        return self.tree(self.mod_root_path / name, files)
+
+    def game(self, game_id, config, files=None):
+        # "path" and "mod_root_path" point into the test folder unless the config sets them
+        game_path = self.root / "games" / game_id
+        self.tree(game_path, files if files is not None else {"game.txt": game_id})
+
+        # Only the keys in front of the first table belong to the game
+        top_level = re.split(r"^\[", config, maxsplit=1, flags=re.MULTILINE)[0]
+        header = ""
+        if not re.search(r"^path =", top_level, re.MULTILINE):
+            header += f'path = "{game_path}"\n'
+        if not re.search(r"^mod_root_path =", top_level, re.MULTILINE):
+            header += f'mod_root_path = "{self.mod_root_path}"\n'
+
+        (self.config_path / f"{game_id}.toml").write_text(header + config)
+        return game_path
+
+    def run(self, *arguments, fail=(), env=None):
+        return subprocess.run([sys.executable, str(script_path), "--no-pkexec", *arguments],
+                              env=dict(self.env, STUB_HELPER_FAIL=" ".join(fail), **(env or {})),
+                              stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, timeout=60)
+
+    def helper_calls(self):
+        if not self.helper_log.exists():
+            return []
+
+        return [line.split(" ") for line in self.helper_log.read_text().splitlines()]
+
+
+@pytest.fixture
+def mod_manager(tmp_path):
+    return ModManager(tmp_path)
+
+
+@pytest.fixture
+def mounting_mod_manager(tmp_path):
+    # Mounts for real with the actual helper instead of recording the calls
+    if os.geteuid() != 0:
+        pytest.skip("Mounting needs root")
+
+    yield ModManager(tmp_path, helper=(script_path.parent / "mod-manager-overlayfs-helper").read_text())
+
+    # Don't leave a failed test mounted
+    with open("/proc/self/mountinfo", "r") as f:
+        mount_points = [line.split(" ")[4] for line in f]
+    for mount_point in reversed(mount_points):
+        if mount_point.startswith(f"{tmp_path}/"):
+            subprocess.run(["umount", "--lazy", mount_point], check=False)
+
+
+@pytest.fixture
+def script(mod_manager, monkeypatch):
+    # The script has no main guard, only load the definitions in front of the argument parsing
+    for variable in ["HOME", "XDG_CONFIG_HOME", "XDG_DATA_HOME", "XDG_CACHE_HOME", "XDG_RUNTIME_DIR"]:
+        monkeypatch.setenv(variable, mod_manager.env[variable])
+
+    from xdg import BaseDirectory
+    importlib.reload(BaseDirectory)
+
+    source = script_path.read_text()
+    namespace = {"__name__": "mod_manager"}
+    exec(compile(source[:source.index("\nclass ArgumentParser(")], str(script_path), "exec"), namespace)
+    yield types.SimpleNamespace(**namespace)
+
+    monkeypatch.undo()
+    importlib.reload(BaseDirectory)
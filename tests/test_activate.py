@@ -0,0 +1,114 @@
+import json
+import os
+from pathlib import Path
+
+import pytest
+
+
+@pytest.mark.parametrize("fail", [["mount"], []])
+def test_failed_mount_moves_the_games_back(mod_manager, fail):
+    # Without "mount" failing the stub mounts nothing which counts as failed, too
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    games = [mod_manager.game(game_id, 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+             for game_id in ["game1", "game2"]]
+
+    result = mod_manager.run("activate", "game1", "game2", fail=fail)
+
+    assert result.returncode == 3
+    assert any(call[0] == "mount" for call in mod_manager.helper_calls())
+    for game_path in games:
+        assert (game_path / "game.txt").read_text() == game_path.name
+        assert not Path(f"{game_path}_mod-manager").exists()
+
+
+def test_unknown_set_activates_none_of_the_games(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    for game_id in ["game1", "game2"]:
+        mod_manager.game(game_id, 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("activate", "game1=typo", "game2")
+
+    assert result.returncode == 2
+    assert "typo" in result.stderr
+    assert mod_manager.helper_calls() == []
+
+
+def test_missing_mod_stops_before_moving_the_game(mod_manager):
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["missing"]\n')
+
+    result = mod_manager.run("activate", "game1")
+
+    assert result.returncode == 2
+    assert "missing' doesn't exist" in result.stderr
+    assert "Traceback" not in result.stderr
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert not Path(f"{game_path}_mod-manager").exists()
+    assert mod_manager.helper_calls() == []
+
+
+def test_missing_mod_keeps_the_current_activation_on_swap(mod_manager):
+    mod_manager.mod("mod1", {"game.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n["set1"]\nmods = ["mod1"]\n'
+                                          '["set2"]\nmods = ["missing"]\n')
+    assert mod_manager.run("activate", "game1").returncode == 0
+
+    result = mod_manager.run("swap", "game1", "set2")
+
+    assert result.returncode == 2
+    assert "missing' doesn't exist" in result.stderr
+    assert (game_path / "game.txt").read_text() == "mod1"
+
+
+@pytest.mark.parametrize("max_lowerdirs, returncode", [(500, 2), (10, 3)])
+def test_too_long_mount_options_stop_before_moving_the_game(mod_manager, max_lowerdirs, returncode):
+    # 25 folders with 200 characters exceed the 4095 bytes of the kernel, chains of 10 don't
+    mods = [f"{number:02}" + "m" * 198 for number in range(25)]
+    for mod in mods:
+        mod_manager.mod(mod, {f"{mod[:2]}.txt": mod[:2]})
+    game_path = mod_manager.game("game1", f'max_lowerdirs = {max_lowerdirs}\nactive = "set1"\n'
+                                          f'["set1"]\nmods = {json.dumps(mods)}\n')
+
+    result = mod_manager.run("activate", "game1")
+
+    assert result.returncode == returncode
+    assert ("the kernel accepts at most 4095" in result.stderr) == (returncode == 2)
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert not Path(f"{game_path}_mod-manager").exists()
+    assert any(call[0] == "mount" for call in mod_manager.helper_calls()) == (returncode == 3)
+
+
+def test_too_long_mount_options_keep_the_current_overlay_on_swap(mounting_mod_manager):
+    mods = [f"{number:02}" + "m" * 198 for number in range(25)]
+    for mod in mods + ["mod1"]:
+        mounting_mod_manager.mod(mod, {f"{mod[:4]}.txt": mod[:4]})
+    game_path = mounting_mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n'
+                                                   f'["set2"]\nmods = {json.dumps(mods)}\n')
+    assert mounting_mod_manager.run("activate", "game1").returncode == 0
+
+    result = mounting_mod_manager.run("swap", "game1", "set2")
+
+    assert result.returncode == 2
+    assert "the kernel accepts at most 4095" in result.stderr
+    assert os.path.ismount(game_path)
+    assert (game_path / "mod1.txt").exists()
+
+    assert mounting_mod_manager.run("deactivate", "game1").returncode == 0
+    assert not os.path.ismount(game_path)
+
+
+@pytest.mark.parametrize("command, message", [
+    ('timeout = 1\ncommand = ["sleep", "10"]\n', "'sleep 10' didn't exit within 1 seconds"),
+    ('command = ["false"]\n', "'false' failed with exit code 1"),
+])
+@pytest.mark.parametrize("arguments", [["activate", "game1"], ["wrap", "game1", "--", "true"]])
+def test_failed_pre_command_rolls_back(mod_manager, command, message, arguments):
+    mod_manager.mod("mod1", {"game.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nrun_pre_command = true\nactive = "set1"\n'
+                                          f'["set1"]\nmods = ["mod1"]\n[[pre_command]]\n{command}')
+
+    result = mod_manager.run(*arguments)
+
+    assert result.returncode == 7
+    assert message in result.stderr
+    assert "Traceback" not in result.stderr
+    assert (game_path / "game.txt").read_text() == "game1"
@@ -0,0 +1,76 @@
+import json
+import os
+
+
+def copy_game(mod_manager, extra=""):
+    mod_manager.mod("mod1", {"game.txt": "mod1", "sub/new.txt": "new", "sub/readme.txt": "readme"})
+    return mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n'
+                                     f'["set1"]\nmods = ["mod1"]\nexclude = ["readme.txt"]\n{extra}')
+
+
+def test_copy_is_restored_on_deactivation(mod_manager):
+    game_path = copy_game(mod_manager)
+
+    assert mod_manager.run("activate", "game1").returncode == 0
+    assert (game_path / "game.txt").read_text() == "mod1"
+    assert (game_path / "sub" / "new.txt").exists()
+    assert not (game_path / "sub" / "readme.txt").exists()
+
+    assert mod_manager.run("deactivate", "game1").returncode == 0
+    assert sorted(path.name for path in game_path.iterdir()) == ["game.txt"]
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert mod_manager.helper_calls() == []
+
+
+def test_interrupted_copy_is_restored(mod_manager):
+    # The manifest is written first, here nothing got backed up or copied yet
+    game_path = copy_game(mod_manager)
+    manifest_path = mod_manager.root / "data" / "mod-manager" / ".copy" / "game1" / "manifest.json"
+    manifest_path.parent.mkdir(parents=True)
+    manifest_path.write_text(json.dumps({"added": ["game.txt", "sub/new.txt"], "created": ["sub"],
+                                         "replaced": ["game.txt"]}))
+
+    assert mod_manager.run("deactivate", "game1").returncode == 0
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert not manifest_path.exists()
+
+
+def test_dry_run_shows_the_copy(mod_manager):
+    game_path = copy_game(mod_manager)
+
+    result = mod_manager.run("activate", "game1", "--dry-run")
+
+    assert result.returncode == 0
+    assert f"Back up '{game_path / 'game.txt'}'" in result.stdout
+    assert f"Copy '{mod_manager.mod_root_path / 'mod1' / 'sub' / 'new.txt'}'" in result.stdout
+    assert "readme.txt" not in result.stdout
+    assert "overlay" not in result.stdout.lower()
+    assert (game_path / "game.txt").read_text() == "game1"
+
+
+def test_copy_rejects_writable_and_bind_mounts(mod_manager):
+    copy_game(mod_manager, 'bind_mounts = { "saves" = "/tmp" }\n')
+    assert mod_manager.run("activate", "game1").returncode == 2
+
+    copy_game(mod_manager)
+    result = mod_manager.run("activate", "game1", "--writable")
+    assert result.returncode == 2
+    assert "overlay" in result.stderr
+
+
+def test_copy_keeps_symlinked_folders_and_folder_permissions(mod_manager):
+    mod_manager.mod("mod1", {"shared/data.txt": "data", "bin/run.sh": "#!/bin/sh\n"})
+    mod_path = mod_manager.mod_root_path / "mod1"
+    (mod_path / "linked").symlink_to("shared")
+    (mod_path / "bin" / "run.sh").chmod(0o755)
+    (mod_path / "bin").chmod(0o750)
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    assert mod_manager.run("activate", "game1").returncode == 0
+    assert (game_path / "linked").is_symlink()
+    assert os.readlink(game_path / "linked") == "shared"
+    assert (game_path / "bin").stat().st_mode & 0o777 == 0o750
+    assert (game_path / "bin" / "run.sh").stat().st_mode & 0o777 == 0o755
+
+    assert mod_manager.run("deactivate", "game1").returncode == 0
+    assert sorted(path.name for path in game_path.iterdir()) == ["game.txt"]
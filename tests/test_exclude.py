@@ -0,0 +1,53 @@
+import pytest
+
+
+@pytest.mark.parametrize("patterns, expected", [
+    (["readme.txt"], ["docs/readme.txt", "readme.txt"]),
+    (["/readme.txt"], ["readme.txt"]),
+    (["docs/*.pdf"], ["docs/manual.pdf"]),
+    (["*.pdf"], ["docs/manual.pdf", "manual.pdf"]),
+    ([".git"], [".git"]),
+    (["docs"], ["docs"]),
+    (["nothing"], []),
+])
+def test_match_excludes(script, mod_manager, patterns, expected):
+    mod = mod_manager.mod("mod1", {"readme.txt": "", "manual.pdf": "", "docs/readme.txt": "",
+                                   "docs/manual.pdf": "", ".git/config": "", "data/file.txt": ""})
+
+    # A hidden folder hides everything below it without a match of its own
+    assert sorted(script.match_excludes(str(mod), patterns)) == expected
+
+
+def test_excludes_only_hide_files_of_their_mods(mod_manager):
+    mod_manager.mod("mod1", {"readme.txt": "mod1", "mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\nexclude = ["readme.txt"]\n',
+                                 {"readme.txt": "game1"})
+
+    # The stub mounts nothing, only the calls until then matter
+    mod_manager.run("activate", "game1", "--writable")
+
+    whiteouts = [call for call in mod_manager.helper_calls() if call[0] == "whiteout"]
+    assert [call[2] for call in whiteouts] == ["readme.txt"]
+    # Not in the upperdir which is shared with the other sets and the game's own readme.txt
+    assert all("/filter/" in call[1] and "persistent" not in call[1] for call in whiteouts)
+    mounts = [call for call in mod_manager.helper_calls() if call[0] == "mount"]
+    assert mounts[0][1] == "game1_filter0"
+    assert f"lowerdir={whiteouts[0][1]}:{mod_manager.mod_root_path / 'mod1'}" in mounts[0][2]
+    assert (game_path / "readme.txt").read_text() == "game1"
+
+
+def test_excluded_files_arent_provided(mod_manager):
+    mod_manager.mod("mod1", {"readme.txt": "mod1", "docs/manual.txt": "mod1", "mod1.txt": "mod1"})
+    mod_manager.mod("mod2", {"readme.txt": "mod2", "docs/manual.txt": "mod2"})
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1", "hidden"]\n'
+                                          '["hidden"]\nmods = ["mod2"]\nexclude = ["readme.txt", "docs"]\n',
+                                 {"readme.txt": "game1"})
+
+    files = mod_manager.run("mods", "files", "game1", "--conflicts")
+    readme = mod_manager.run("whereis", "game1", "readme.txt")
+    manual = mod_manager.run("whereis", "game1", "docs/manual.txt")
+
+    assert files.returncode == 0, files.stderr
+    assert files.stdout == ""
+    assert readme.stdout.strip() in [str(mod_manager.mod_root_path / "mod1"), str(game_path)]
+    assert manual.stdout.strip() == str(mod_manager.mod_root_path / "mod1")
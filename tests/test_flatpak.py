@@ -0,0 +1,34 @@
+def flatpak_game(mod_manager, path):
+    return mod_manager.game("game1", f'active = "set1"\npath = "{path}"\nflatpak_app = "com.example.Game"\n'
+                                     '["set1"]\nmods = []\n')
+
+
+def test_path_is_relative_to_the_app_data(mod_manager):
+    game_path = mod_manager.root / "home" / ".var" / "app" / "com.example.Game" / "data" / "my game"
+    mod_manager.tree(game_path, {"game.txt": "game1"})
+    flatpak_game(mod_manager, "data/my game")
+
+    result = mod_manager.run("status", "game1")
+
+    assert result.returncode == 0, result.stderr
+    assert str(game_path) in result.stdout
+
+
+def test_missing_app_data_is_a_config_error(mod_manager):
+    flatpak_game(mod_manager, "data/my game")
+
+    result = mod_manager.run("status", "game1")
+
+    assert result.returncode == 2
+    assert "com.example.Game" in result.stderr
+    assert "Traceback" not in result.stderr
+
+
+def test_absolute_path_is_a_config_error(mod_manager):
+    (mod_manager.root / "home" / ".var" / "app" / "com.example.Game").mkdir(parents=True)
+    flatpak_game(mod_manager, mod_manager.root / "games" / "game1")
+
+    result = mod_manager.run("status", "game1")
+
+    assert result.returncode == 2
+    assert "'path' has to be relative" in result.stderr
@@ -0,0 +1,56 @@
+import json
+
+import pytest
+
+
+def json_lines(result):
+    # Every line of stdout has to be parseable, nothing else may end up in between
+    return [json.loads(line) for line in result.stdout.splitlines()]
+
+
+@pytest.mark.parametrize("arguments", [["--format", "json", "list"], ["list", "--json"]])
+def test_list_as_json_lines(mod_manager, arguments):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+    (mod_manager.config_path / "broken.toml").write_text('active = "set1"\n')
+
+    result = mod_manager.run(*arguments)
+
+    assert result.returncode == 0
+    assert result.stderr == ""
+    events = json_lines(result)
+    assert {"event": "warning", "game": "broken", "kind": "config"}.items() <= events[0].items()
+    assert events[1] == {"event": "game", "game": "game1", "active": "set1", "state": "normal"}
+
+
+def test_config_errors_of_sets_as_json(mod_manager):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["set2"]\n["set2"]\nmods = ["set1"]\n')
+
+    result = mod_manager.run("--format", "json", "activate", "game1")
+
+    assert result.returncode == 2
+    assert result.stderr == ""
+    assert any(event["event"] == "error" and "Recursive mod set" in event["message"]
+               for event in json_lines(result))
+
+
+def test_repair_and_doctor_as_json(mod_manager):
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+    mod_manager.tree(game_path.parent / "game1_mod-manager", {"game.txt": "moved"})
+
+    repair = mod_manager.run("--format", "json", "repair", "game1")
+    doctor = mod_manager.run("--format", "json", "doctor")
+
+    assert repair.returncode != 0 and repair.stderr == ""
+    assert all(event["game"] == "game1" for event in json_lines(repair))
+    assert doctor.stderr == ""
+    assert any(event["event"] == "check" and event["check"] == "Game 'game1'" for event in json_lines(doctor))
+
+
+def test_clean_all_doesnt_prompt_into_json(mod_manager):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+
+    result = mod_manager.run("--format", "json", "clean", "game1", "--all")
+
+    assert result.returncode == 1
+    assert json_lines(result)[-1]["kind"] == "clean"
@@ -0,0 +1,68 @@
+import errno
+import os
+import shutil
+
+import pytest
+
+
+@pytest.fixture
+def cross_device(script, monkeypatch):
+    # Every rename fails like it would between two file systems
+    def rename(source, destination):
+        raise OSError(errno.EXDEV, os.strerror(errno.EXDEV), source)
+
+    monkeypatch.setattr(script.os, "rename", rename)
+
+
+def test_moving_across_file_systems_needs_the_copy_strategy(script, mod_manager, cross_device):
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+    game = script.Game("game1")
+
+    with pytest.raises(SystemExit) as error:
+        game._Game__move_game_folder(str(game_path), f"{game_path}_mod-manager")
+
+    assert error.value.code == script.ExitCode.MOUNT
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert not os.path.exists(f"{game_path}_mod-manager")
+
+
+def test_copy_strategy_copies_across_file_systems(script, mod_manager, cross_device):
+    game_path = mod_manager.game("game1", 'move_strategy = "copy"\nactive = "set1"\n["set1"]\nmods = []\n',
+                                 {"game.txt": "game1", "data/level.dat": "level"})
+    game = script.Game("game1")
+
+    game._Game__move_game_folder(str(game_path), f"{game_path}_mod-manager")
+
+    assert not game_path.exists()
+    moved_path = game_path.with_name("game1_mod-manager")
+    assert (moved_path / "game.txt").read_text() == "game1"
+    assert (moved_path / "data" / "level.dat").read_text() == "level"
+
+
+def test_failed_copy_back_isnt_retried(script, mod_manager, cross_device, monkeypatch):
+    game_path = mod_manager.game("game1", 'move_strategy = "copy"\nmove_back_retries = 2\nactive = "set1"\n'
+                                          '["set1"]\nmods = []\n', {"a.txt": "a", "b.txt": "b"})
+    moved_path = game_path.with_name("game1_mod-manager")
+    shutil.copytree(game_path, moved_path)
+    shutil.rmtree(game_path)
+
+    # The second file doesn't fit anymore
+    copies = []
+
+    def copy2(source, destination, **kwargs):
+        copies.append(source)
+        if len(copies) == 2:
+            raise OSError(errno.ENOSPC, os.strerror(errno.ENOSPC), destination)
+        return original_copy2(source, destination, **kwargs)
+
+    original_copy2 = script.shutil.copy2
+    monkeypatch.setattr(script.shutil, "copy2", copy2)
+    game = script.Game("game1")
+
+    with pytest.raises(SystemExit) as error:
+        game.deactivate()
+
+    assert error.value.code == script.ExitCode.MOUNT
+    assert len(copies) == 2
+    assert not (game_path / "game1_mod-manager").exists()
+    assert sorted(path.name for path in moved_path.iterdir()) == ["a.txt", "b.txt"]
@@ -0,0 +1,133 @@
+import pytest
+
+
+@pytest.mark.parametrize("path, expected", [
+    ("~/mods", "{home}/mods"),
+    ("$MOD_MANAGER_TEST/mods", "/test/mods"),
+    ("${MOD_MANAGER_TEST}mods", "/testmods"),
+    ("$GAME/logs", "/games/game1/logs"),
+    ("/plain/path", "/plain/path"),
+])
+def test_expand_path(script, monkeypatch, mod_manager, path, expected):
+    monkeypatch.setenv("MOD_MANAGER_TEST", "/test")
+
+    assert script.expand_path(path, {"GAME": "/games/game1"}) == expected.format(home=mod_manager.env["HOME"])
+
+
+def test_expand_path_fails_on_unset_variables(script, monkeypatch):
+    monkeypatch.delenv("MOD_MANAGER_UNSET", raising=False)
+
+    with pytest.raises(ValueError):
+        script.expand_path("$MOD_MANAGER_UNSET/mods")
+
+
+@pytest.mark.parametrize("config, arguments", [
+    ('["set1".bind_mounts]\n"saves" = "$MOD_MANAGER_UNSET/saves"\n', ["activate", "game1"]),
+    ('["set1".bind_mounts]\n"saves" = "$MOD_MANAGER_UNSET/saves"\n', ["activate", "game1", "--print-only"]),
+    ('environment_file = "$MOD_MANAGER_UNSET/game.env"\n', ["printenv", "game1"]),
+    ('["set1"]\nenvironment_file = "$MOD_MANAGER_UNSET/set.env"\n', ["wrap", "game1", "--", "true"]),
+])
+def test_unset_variables_fail_where_the_path_is_used(mod_manager, config, arguments):
+    mod_manager.game("game1", 'active = "set1"\n' + config + ("" if '["set1"]' in config else '["set1"]\n')
+                     + "mods = []\n", {"game.txt": "game1", "saves": None})
+
+    result = mod_manager.run(*arguments)
+
+    assert result.returncode == 2
+    assert "MOD_MANAGER_UNSET" in result.stderr
+    assert mod_manager.helper_calls() == []
+
+
+def test_unset_variables_dont_stop_deactivate(mod_manager):
+    # e.g. a systemd unit deactivating without the variables of the game's prefix
+    mod_manager.mod("mod1", {"game.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n'
+                                          'crash_log_glob = "*.log"\ncrash_log_path = "$MOD_MANAGER_UNSET/logs"\n'
+                                          'crash_log_copy_to = "$MOD_MANAGER_UNSET/copies"\n'
+                                          'environment_file = "$MOD_MANAGER_UNSET/game.env"\n'
+                                          '["set1"]\nmods = ["mod1"]\n')
+    assert mod_manager.run("activate", "game1").returncode == 0
+
+    for arguments in [["status", "game1"], ["deactivate", "game1"], ["repair", "game1"]]:
+        result = mod_manager.run(*arguments)
+        assert result.returncode == 0, result.stderr
+
+    assert (game_path / "game.txt").read_text() == "game1"
+
+
+def test_unset_variables_of_the_crash_log_are_a_warning(mod_manager):
+    mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n'
+                              'crash_log_glob = "*.log"\ncrash_log_path = "$MOD_MANAGER_UNSET/logs"\n'
+                              '["set1"]\nmods = []\n')
+
+    result = mod_manager.run("wrap", "game1", "--", "sh", "-c", "exit 3")
+
+    assert result.returncode == 3
+    assert "MOD_MANAGER_UNSET" in result.stderr
+
+
+def test_environment_files_are_expanded(mod_manager):
+    mod_manager.tree(mod_manager.root / "env", {"game.env": "GAME_VARIABLE=game\n", "set.env": "SET_VARIABLE=set\n"})
+    mod_manager.game("game1", f'active = "set1"\nenvironment_file = "$MOD_MANAGER_TEST/game.env"\n'
+                              '["set1"]\nenvironment_file = "${MOD_MANAGER_TEST}/set.env"\nmods = []\n')
+
+    result = mod_manager.run("printenv", "game1", env={"MOD_MANAGER_TEST": str(mod_manager.root / "env")})
+
+    assert result.returncode == 0, result.stderr
+    assert "export GAME_VARIABLE=game\n" in result.stdout
+    assert "export SET_VARIABLE=set\n" in result.stdout
+
+
+def test_symlinked_game_path_moves_its_target(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", f'path = "{mod_manager.root / "link"}"\nactive = "set1"\n'
+                                          '["set1"]\nmods = ["mod1"]\n')
+    (mod_manager.root / "link").symlink_to(mod_manager.root / "games" / "game1")
+
+    result = mod_manager.run("activate", "game1", "--print-only")
+
+    assert result.returncode == 0, result.stderr
+    assert result.stdout.splitlines()[0] == f"mv -- {game_path} {game_path}_mod-manager"
+    assert result.stdout.splitlines()[-1].endswith(f" {game_path}")
+
+
+def test_trailing_slash_stays_out_of_the_moved_path(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", f'path = "{mod_manager.root / "games" / "game1"}/"\nactive = "set1"\n'
+                                          '["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("activate", "game1", "--print-only")
+
+    assert result.returncode == 0, result.stderr
+    assert result.stdout.splitlines()[0] == f"mv -- {game_path} {game_path}_mod-manager"
+
+
+@pytest.mark.parametrize("mod, expected", [
+    ("mod1", "/mods/mod1"),
+    ("nested/mod1", "/mods/nested/mod1"),
+    ("/shared/mod1", "/shared/mod1"),
+    ("~/mod1", "{home}/mod1"),
+    ("$MOD_MANAGER_TEST/mod1", "/test/mod1"),
+])
+def test_mod_path(script, monkeypatch, mod_manager, mod, expected):
+    monkeypatch.setenv("MOD_MANAGER_TEST", "/test")
+
+    assert script.mod_path("/mods", mod) == expected.format(home=mod_manager.env["HOME"])
+
+
+def test_mods_outside_of_the_mod_root(mod_manager):
+    mod_manager.mod("mod1", {"mod1.txt": "mod1"})
+    mod_manager.mod("nested/mod2", {"mod2.txt": "mod2"})
+    mod_manager.tree(mod_manager.root / "shared", {"mod3/mod3.txt": "mod3", "mod4/mod4.txt": "mod4"})
+    mod_manager.tree(mod_manager.root / "home", {"mod5/mod5.txt": "mod5"})
+    mod_manager.game("game1", f'active = "set1"\n["set1"]\nmods = ["mod1", "nested/mod2", '
+                              f'"{mod_manager.root / "shared" / "mod3"}", "$MOD_MANAGER_TEST/mod4", "~/mod5"]\n')
+
+    result = mod_manager.run("mods", "list", "game1", env={"MOD_MANAGER_TEST": str(mod_manager.root / "shared")})
+
+    assert result.returncode == 0, result.stderr
+    layers = [line.split(maxsplit=1)[1] for line in result.stdout.splitlines()]
+    expected = [mod_manager.mod_root_path / "mod1", mod_manager.mod_root_path / "nested" / "mod2",
+                mod_manager.root / "shared" / "mod3", mod_manager.root / "shared" / "mod4",
+                mod_manager.root / "home" / "mod5"]
+    assert sorted(layers) == sorted(str(path) for path in expected)
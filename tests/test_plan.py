@@ -0,0 +1,111 @@
+import json
+import os
+
+import pytest
+
+
+@pytest.mark.parametrize("arguments", [["activate", "--dry-run"], ["activate", "--print-only"], ["setup", "--dry-run"]])
+def test_planning_works_without_the_helper(mod_manager, arguments):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run(*arguments, "game1", *(["new-mod"] if arguments[0] == "setup" else []),
+                             env={"PATH": os.environ["PATH"]})
+
+    assert result.returncode == 0, result.stderr
+    assert str(game_path) in result.stdout
+
+
+def test_print_only_copies_copy_games(mod_manager):
+    mod_manager.mod("mod1", {"game.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("activate", "game1", "--print-only")
+
+    assert result.returncode == 0, result.stderr
+    lines = result.stdout.splitlines()
+    assert "manifest.json" in lines[1]
+    assert any(line.startswith("mv -- ") and line.endswith("backup/game.txt") for line in lines)
+    assert f"cp -a -- {mod_manager.mod_root_path / 'mod1' / 'game.txt'} {game_path / 'game.txt'}" in lines
+    assert "mount" not in result.stdout
+
+
+def test_print_only_pipes_the_mount_options(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("--helper-stdin", "activate", "game1", "--print-only")
+
+    assert result.returncode == 0, result.stderr
+    mount = result.stdout.splitlines()[-1]
+    options, command = mount.split(" | ")
+    assert options.startswith("printf '%s\\n' ") and "lowerdir=" in options
+    assert "mount-stdin game1 " in command and "lowerdir=" not in command
+
+
+@pytest.mark.parametrize("config, options", [
+    ("", "index=off,metacopy=off"),
+    ("index = false\n", "index=off,metacopy=off"),
+    ("index = true\n", "index=on,metacopy=off"),
+])
+def test_index_is_opt_in(mod_manager, config, options):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    mod_manager.game("game1", config + 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("activate", "game1", "--print-only")
+
+    assert result.returncode == 0, result.stderr
+    mount = result.stdout.splitlines()[-1]
+    assert f",{options},lowerdir=" in mount
+
+
+def test_set_priority_applies_to_its_mods_and_command(mod_manager):
+    for name in ["mod1", "mod2", "mod3", "mod4"]:
+        mod_manager.mod(name, {f"{name}.txt": name})
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["low", "high", "mod3"]\n'
+                              '["low"]\nmods = ["mod1", { mod = "mod4", priority = 20 }]\ncommand = "command_low"\n'
+                              '["high"]\npriority = 10\nmods = ["mod2"]\ncommand = "command_high"\n'
+                              '["command_low"]\ncommand = ["echo", "low"]\n'
+                              '["command_high"]\ncommand = ["echo", "high"]\n')
+
+    result = mod_manager.run("activate", "game1", "--dry-run")
+
+    assert result.returncode == 0, result.stderr
+    lowerdirs = result.stdout.split("lowerdir=")[1].split("'")[0].split(":")
+    assert [os.path.basename(lowerdir) for lowerdir in lowerdirs[:-1]] == ["mod4", "mod2", "mod1", "mod3"]
+    commands = [line for line in result.stdout.splitlines() if line.startswith("Run command ")]
+    assert commands == ["Run command ['echo', 'high']", "Run command ['echo', 'low']"]
+
+
+@pytest.mark.parametrize("max_lowerdirs, mods, error", [
+    (2, 3, "3 mods are too many for 'max_lowerdirs = 2', it allows at most 2"),
+    (10, 91, "91 mods are too many for 'max_lowerdirs = 10', it allows at most 90"),
+    (10, 90, None),
+])
+def test_chained_overlays_are_limited(mod_manager, max_lowerdirs, mods, error):
+    names = [f"mod{number}" for number in range(mods)]
+    for name in names:
+        mod_manager.mod(name, {f"{name}.txt": name})
+    mod_manager.game("game1", f'max_lowerdirs = {max_lowerdirs}\nactive = "set1"\n'
+                              f'["set1"]\nmods = {json.dumps(names)}\n')
+
+    result = mod_manager.run("activate", "game1", "--print-only")
+
+    assert result.returncode == (0 if error is None else 2), result.stderr
+    assert error is None or error in result.stderr
+    assert "Traceback" not in result.stderr
+
+
+def test_filtered_mods_arent_chained(mod_manager):
+    # filter overlay -> chain overlay -> main overlay is deeper than the kernel stacks
+    names = [f"mod{number}" for number in range(5)]
+    for name in names:
+        mod_manager.mod(name, {f"{name}.txt": name, "readme.txt": name})
+    mod_manager.game("game1", f'max_lowerdirs = 3\nactive = "set1"\n'
+                              f'["set1"]\nmods = {json.dumps(names)}\nexclude = ["readme.txt"]\n')
+
+    result = mod_manager.run("activate", "game1")
+
+    assert result.returncode == 2
+    assert "Mods with 'exclude' can't be chained" in result.stderr
+    assert mod_manager.helper_calls() == []
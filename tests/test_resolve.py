@@ -0,0 +1,42 @@
+import json
+
+import pytest
+
+
+# --set, MOD_MANAGER_SET, last set with the "active" it was saved with -> expected output of --why-set
+@pytest.mark.parametrize("cli_set, env_set, last_set, expected", [
+    (None, None, None, "set1 (from config)"),
+    ("set2", None, None, "set2 (from command line)"),
+    (None, "set2", None, "set2 (from MOD_MANAGER_SET)"),
+    (None, None, ("set3", "set1"), "set3 (from last activation)"),
+    ("set2", "set3", ("set3", "set1"), "set2 (from command line)"),
+    (None, "set2", ("set3", "set1"), "set2 (from MOD_MANAGER_SET)"),
+    # Sets other games define are skipped
+    (None, "other", ("set3", "set1"), "set3 (from last activation)"),
+    (None, "other", None, "set1 (from config)"),
+    # "active" got changed since
+    (None, None, ("set3", "set2"), "set1 (from config)"),
+    # Removed from the config since
+    (None, None, ("gone", "set1"), "set1 (from config)"),
+])
+def test_set_precedence(mod_manager, cli_set, env_set, last_set, expected):
+    mod_manager.game("game1", 'active = "set1"\n' + "".join(f'["set{number}"]\nmods = []\n' for number in [1, 2, 3]))
+    if last_set is not None:
+        last_set_path = mod_manager.root / "cache" / "mod-manager" / "game1" / "last_set.json"
+        last_set_path.parent.mkdir(parents=True)
+        last_set_path.write_text(json.dumps({"set": last_set[0], "active": last_set[1]}))
+
+    result = mod_manager.run("resolve", "game1", "--why-set", *(["--set", cli_set] if cli_set else []),
+                             env={"MOD_MANAGER_SET": env_set} if env_set else None)
+
+    assert result.returncode == 0, result.stderr
+    assert result.stdout == f"{expected}\n"
+
+
+def test_activation_with_set_is_remembered(mod_manager):
+    mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n["set1"]\nmods = []\n["set2"]\nmods = []\n')
+
+    assert mod_manager.run("activate", "game1=set2").returncode == 0
+    assert mod_manager.run("deactivate", "game1").returncode == 0
+
+    assert mod_manager.run("resolve", "game1", "--why-set").stdout == "set2 (from last activation)\n"
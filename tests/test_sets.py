@@ -0,0 +1,71 @@
+import pytest
+
+
+def nested_root_game(mod_manager):
+    other_root = mod_manager.root / "other"
+    mod_manager.mod("mod1", {"mod1.txt": "mod1"})
+    mod_manager.tree(other_root, {"mod2/mod2.txt": "mod2", "mod3/mod3.txt": "mod3"})
+    mod_manager.game("game1", f'active = "set1"\n["set1"]\nmods = ["mod1", "other"]\n'
+                              f'["other"]\nmod_root_path = "{other_root}"\nmods = ["mod2", "nested"]\n'
+                              '["nested"]\nmods = ["mod3"]\n')
+    return other_root
+
+
+def test_sets_pull_their_mods_from_their_own_root(mod_manager):
+    other_root = nested_root_game(mod_manager)
+
+    result = mod_manager.run("mods", "list", "game1")
+
+    assert result.returncode == 0, result.stderr
+    layers = [line.split(maxsplit=1)[1] for line in result.stdout.splitlines()]
+    assert sorted(layers) == sorted([str(mod_manager.mod_root_path / "mod1"),
+                                     str(other_root / "mod2"), str(other_root / "mod3")])
+
+
+def test_doctor_checks_nested_sets_in_the_root_of_their_parent(mod_manager):
+    other_root = nested_root_game(mod_manager)
+
+    result = mod_manager.run("doctor")
+
+    assert "doesn't exist" not in result.stdout
+
+    (other_root / "mod3" / "mod3.txt").unlink()
+    (other_root / "mod3").rmdir()
+
+    result = mod_manager.run("doctor")
+
+    assert "Mod 'mod3' of set 'nested' doesn't exist" in result.stdout
+
+
+@pytest.mark.parametrize("mods, cycle", [
+    ('[{ mod = "mod1", after = ["mod2"] }, { mod = "mod2", after = ["mod1"] }]', "mod1 -> mod2 -> mod1"),
+    # "mod3" is only overridden, it's not part of the loop itself
+    ('["mod3", { mod = "mod1", after = ["mod2", "mod3"] }, { mod = "mod2", after = ["mod1"] }]',
+     "mod1 -> mod2 -> mod1"),
+])
+def test_contradicting_load_order(mod_manager, mods, cycle):
+    for name in ["mod1", "mod2", "mod3"]:
+        mod_manager.mod(name, {f"{name}.txt": name})
+    mod_manager.game("game1", f'active = "set1"\n["set1"]\nmods = {mods}\n')
+
+    result = mod_manager.run("mods", "list", "game1")
+
+    assert result.returncode == 2
+    assert f"Contradicting load order: {cycle}" in result.stderr
+    assert "Traceback" not in result.stderr
+
+
+def test_mods_of_different_roots_can_share_a_name(mod_manager):
+    other_root = mod_manager.root / "other"
+    mod_manager.mod("mod1", {"mod1.txt": "mod1"})
+    mod_manager.tree(other_root, {"mod1/other.txt": "other"})
+    # The absolute path is the same mod as "mod1"
+    mod_manager.game("game1", f'active = "set1"\n'
+                              f'["set1"]\nmods = ["mod1", "other", "{mod_manager.mod_root_path}/mod1"]\n'
+                              f'["other"]\nmod_root_path = "{other_root}"\nmods = ["mod1"]\n')
+
+    result = mod_manager.run("mods", "list", "game1")
+
+    assert result.returncode == 0, result.stderr
+    layers = [line.split(maxsplit=1)[1] for line in result.stdout.splitlines()]
+    assert sorted(layers) == sorted([str(mod_manager.mod_root_path / "mod1"), str(other_root / "mod1")])
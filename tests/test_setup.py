@@ -0,0 +1,100 @@
+import shutil
+import tempfile
+from pathlib import Path
+
+import pytest
+
+
+def base_set_with_excludes(mod_manager):
+    mod_manager.mod("mod1", {"readme.txt": "mod1", "mod.txt": "mod1"})
+    return mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\nexclude = ["readme.txt"]\n')
+
+
+def test_setup_keeps_the_whiteouts_of_the_base_set_out_of_the_new_mod(mod_manager):
+    base_set_with_excludes(mod_manager)
+
+    result = mod_manager.run("setup", "game1", "new-mod", "--set", "set1", "--no-prompt", "--dry-run")
+
+    assert result.returncode == 0, result.stderr
+    whiteout_steps = [line for line in result.stdout.splitlines() if line.startswith("Hide ")]
+    assert len(whiteout_steps) == 1
+    assert "/filter/" in whiteout_steps[0] and "persistent_setup" not in whiteout_steps[0]
+
+
+def test_setup_creates_no_whiteouts_in_its_upperdir(mod_manager):
+    base_set_with_excludes(mod_manager)
+
+    # The stub mounts nothing, only the calls until then matter
+    mod_manager.run("setup", "game1", "new-mod", "--set", "set1", "--no-prompt")
+
+    whiteouts = [call for call in mod_manager.helper_calls() if call[0] == "whiteout"]
+    assert [call[2] for call in whiteouts] == ["readme.txt"]
+    assert all("persistent_setup" not in call[1] for call in whiteouts)
+    assert not (mod_manager.mod_root_path / "new-mod").exists()
+
+
+def test_setup_dry_run_creates_no_collect_folder(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+    collect_path = mod_manager.root / "collected"
+
+    result = mod_manager.run("setup", "game1", "new-mod", "--collect-to", str(collect_path), "--dry-run")
+
+    assert result.returncode == 0, result.stderr
+    assert f"Create '{collect_path}'" in result.stdout
+    assert not collect_path.exists()
+
+
+def test_setup_verbose_is_the_global_verbose(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+
+    before = mod_manager.run("-v", "setup", "game1", "new-mod", "--dry-run")
+    after = mod_manager.run("setup", "game1", "new-mod", "-v", "--dry-run")
+
+    assert before.returncode == after.returncode == 0
+    assert "State of 'game1'" in before.stdout + before.stderr
+    assert before.stdout == after.stdout and before.stderr == after.stderr
+
+
+@pytest.mark.parametrize("subtree", ["/etc", "..", "../other-game", "Data/../../other-game", "."])
+def test_setup_subtree_stays_inside_of_the_game(mod_manager, subtree):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+
+    result = mod_manager.run("setup", "game1", "new-mod", "--subtree", subtree, "--no-prompt")
+
+    assert result.returncode == 1
+    assert "--subtree" in result.stderr
+    assert mod_manager.helper_calls() == []
+
+
+def test_setup_subtree_is_normalized(mod_manager):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+
+    result = mod_manager.run("setup", "game1", "new-mod", "--subtree", "Data/./Textures/", "--dry-run")
+
+    assert result.returncode == 0, result.stderr
+    assert f"'{mod_manager.mod_root_path / 'new-mod' / 'Data' / 'Textures'}'" in result.stdout
+
+
+def test_setup_skipping_special_files_succeeds(mounting_mod_manager):
+    # A deleted game file is a whiteout in the upperdir, which can't be copied to another file system
+    collect_path = Path(tempfile.mkdtemp(dir="/dev/shm"))
+    if collect_path.stat().st_dev == mounting_mod_manager.root.stat().st_dev:
+        pytest.skip("Needs the collect folder on another file system")
+
+    mounting_mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mounting_mod_manager.game("game1", 'run_pre_command = true\nactive = "set1"\n'
+                                                   '["set1"]\nmods = ["mod1"]\n'
+                                                   '[[pre_command]]\ncwd = "$GAME"\ncommand = ["rm", "game.txt"]\n'
+                                                   '[[pre_command]]\ncwd = "$GAME"\ncommand = ["touch", "new.txt"]\n')
+
+    try:
+        result = mounting_mod_manager.run("setup", "game1", "new-mod", "--no-prompt", "--collect-to", str(collect_path))
+
+        assert result.returncode == 0, result.stderr
+        assert "1 entries skipped" in result.stderr
+        assert (collect_path / "new-mod" / "new.txt").exists()
+        assert (game_path / "game.txt").exists()
+    finally:
+        shutil.rmtree(collect_path)
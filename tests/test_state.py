@@ -0,0 +1,132 @@
+import json
+import os
+import shutil
+import stat
+import tempfile
+from pathlib import Path
+
+import pytest
+
+
+def invalid_game(mod_manager):
+    # Both the game folder and its moved copy have files, only `repair` can tell which is which
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n')
+    mod_manager.tree(game_path.parent / "game1_mod-manager", {"game.txt": "moved"})
+    return game_path
+
+
+@pytest.mark.parametrize("arguments", [["activate"], ["activate", "--dry-run"], ["activate", "--print-only"]])
+def test_activating_an_invalid_game_asks_for_repair(mod_manager, arguments):
+    game_path = invalid_game(mod_manager)
+
+    result = mod_manager.run(*arguments, "game1")
+
+    assert result.returncode == 6
+    assert "mod-manager repair game1" in result.stderr
+    assert "Traceback" not in result.stderr
+    assert mod_manager.helper_calls() == []
+    assert (game_path / "game.txt").read_text() == "game1"
+
+
+def test_deactivating_an_invalid_game_asks_for_repair(mod_manager):
+    game_path = invalid_game(mod_manager)
+
+    result = mod_manager.run("deactivate", "game1")
+
+    assert result.returncode == 6
+    assert "mod-manager repair game1" in result.stderr
+    assert "Traceback" not in result.stderr
+    assert (game_path / "game.txt").read_text() == "game1"
+    assert (game_path.parent / "game1_mod-manager" / "game.txt").read_text() == "moved"
+
+
+def test_keep_going_removes_the_ephemeral_upperdir(mod_manager):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n')
+    ephemeral_upperdir = mod_manager.tree(mod_manager.root / "cache" / "mod-manager" / "game1" / "ephemeral_1",
+                                          {"written.txt": "written"})
+    activation_path = mod_manager.root / "runtime" / "mod-manager" / "game1.json"
+    activation_path.parent.mkdir(parents=True)
+    activation_path.write_text(json.dumps({"game": "game1", "set": "set1", "writable": True,
+                                           "persistent_name": None, "ephemeral_upperdir": str(ephemeral_upperdir)}))
+
+    result = mod_manager.run("deactivate", "game1", "--keep-going")
+
+    assert result.returncode == 0, result.stderr
+    assert not ephemeral_upperdir.exists()
+    assert not activation_path.exists()
+
+
+@pytest.mark.parametrize("game_set, sessions", [("a", ["s1"]), ("a_b", ["s2"])])
+def test_sessions_of_prefixed_sets_stay_apart(script, mod_manager, game_set, sessions):
+    mod_manager.game("game1", 'active = "a"\n["a"]\nmods = []\n["a_b"]\nmods = []\n')
+    persistent_root = mod_manager.root / "data" / "mod-manager" / ".persistent" / "game1"
+    for name in ["a_persistent", "a_s1_persistent", "a_b_persistent", "a_b_s2_persistent"]:
+        (persistent_root / name).mkdir(parents=True)
+
+    assert script.Game("game1", game_set).get_sessions() == sessions
+
+
+def test_reset_upper_refuses_an_activated_game(mod_manager):
+    mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\n["set1"]\nmods = []\n')
+    assert mod_manager.run("activate", "game1").returncode == 0
+
+    result = mod_manager.run("reset-upper", "game1")
+
+    assert result.returncode == 4
+    assert "deactivate it first" in result.stderr
+    assert "Traceback" not in result.stderr
+
+
+def old_upperdir_game(mod_manager):
+    mod_manager.mod("mod1", {"mod.txt": "mod1"})
+    game_path = mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = ["mod1"]\n',
+                                 {"game.txt": "game1", "deleted.txt": "deleted"})
+    old_upperdir = mod_manager.tree(mod_manager.root / "cache" / "mod-manager" / "game1" / "set1_persistent",
+                                    {"written.txt": "written"})
+    # The whiteout of "deleted.txt"
+    if os.geteuid() != 0:
+        pytest.skip("Creating a whiteout needs root")
+    os.mknod(old_upperdir / "deleted.txt", stat.S_IFCHR, os.makedev(0, 0))
+    return game_path, old_upperdir
+
+
+def test_old_upperdir_stays_for_read_only_activations(mod_manager):
+    _, old_upperdir = old_upperdir_game(mod_manager)
+
+    mod_manager.run("activate", "game1")
+
+    assert (old_upperdir / "written.txt").exists()
+    assert not (mod_manager.root / "data" / "mod-manager" / ".persistent" / "game1" / "set1_persistent").exists()
+
+
+def test_old_upperdir_stays_across_file_systems(mod_manager):
+    _, old_upperdir = old_upperdir_game(mod_manager)
+    data_path = Path(tempfile.mkdtemp(dir="/dev/shm"))
+
+    try:
+        if data_path.stat().st_dev == mod_manager.root.stat().st_dev:
+            pytest.skip("Needs $XDG_DATA_HOME on another file system")
+
+        result = mod_manager.run("activate", "--writable", "game1", env={"XDG_DATA_HOME": str(data_path)})
+
+        assert result.returncode == 3
+        assert "have to be on the same file system" in result.stderr
+        assert (old_upperdir / "written.txt").exists()
+        assert stat.S_ISCHR(os.lstat(old_upperdir / "deleted.txt").st_mode)
+        assert mod_manager.helper_calls() == []
+    finally:
+        shutil.rmtree(data_path)
+
+
+def test_old_upperdir_moves_with_its_whiteouts(mounting_mod_manager):
+    game_path, old_upperdir = old_upperdir_game(mounting_mod_manager)
+
+    result = mounting_mod_manager.run("activate", "--writable", "game1")
+
+    assert result.returncode == 0, result.stderr
+    assert (game_path / "written.txt").read_text() == "written"
+    assert not (game_path / "deleted.txt").exists()
+    assert not old_upperdir.exists()
+
+    assert mounting_mod_manager.run("deactivate", "game1").returncode == 0
@@ -0,0 +1,52 @@
+import json
+
+import pytest
+
+
+@pytest.mark.parametrize("activation, options, mismatch", [
+    ({}, {}, None),
+    ({}, {"game_set": "set2"}, "the set 'set1' is activated instead of 'set2'"),
+    ({}, {"writable": True}, "it's activated read-only"),
+    ({"writable": True, "persistent_name": "set1_persistent"}, {}, "it's activated writable"),
+    ({"writable": True, "persistent_name": "set1_persistent"}, {"writable": True}, None),
+    ({"writable": True, "persistent_name": "set1_a_persistent"}, {"writable": True},
+     "the written changes go to 'set1_a_persistent' instead of 'set1_persistent'"),
+    ({"writable": True, "ephemeral_upperdir": "/tmp/ephemeral_1"}, {"writable": True},
+     "it's activated with --ephemeral"),
+    ({"writable": True, "ephemeral_upperdir": "/tmp/ephemeral_1"}, {"ephemeral": True}, None),
+])
+def test_activation_mismatch(script, mod_manager, activation, options, mismatch):
+    mod_manager.game("game1", 'active = "set1"\n["set1"]\nmods = []\n["set2"]\nmods = []\n')
+    activation_path = mod_manager.root / "runtime" / "mod-manager" / "game1.json"
+    activation_path.parent.mkdir(parents=True, exist_ok=True)
+    activation_path.write_text(json.dumps(dict({"game": "game1", "set": "set1", "writable": False,
+                                                "persistent_name": None, "ephemeral_upperdir": None},
+                                               **activation)))
+
+    game = script.Game("game1", options.pop("game_set", None))
+
+    assert game.get_activation_mismatch(**options) == mismatch
+
+
+def test_failed_crash_log_copy_keeps_the_exit_code(mod_manager):
+    # A file is in the way of the folder to copy to
+    game_path = mod_manager.game("game1", 'strategy = "copy"\nactive = "set1"\ncrash_log_glob = "*.log"\n'
+                                          'crash_log_copy_to = "$GAME/game.txt/logs"\n["set1"]\nmods = []\n',
+                                 {"game.txt": "game1", "crash.log": "crash"})
+
+    result = mod_manager.run("wrap", "game1", "--", "sh", "-c", "exit 3")
+
+    assert result.returncode == 3
+    assert f"Newest crash log: '{game_path / 'crash.log'}'" in result.stderr
+    assert "Reporting the crash log failed" in result.stderr
+    assert "Running 'sh' failed" not in result.stderr
+
+
+def test_absolute_crash_log_glob_is_a_config_error(mod_manager):
+    mod_manager.game("game1", 'active = "set1"\ncrash_log_glob = "/var/log/*.log"\n["set1"]\nmods = []\n')
+
+    result = mod_manager.run("wrap", "game1", "--", "true")
+
+    assert result.returncode == 2
+    assert "'crash_log_glob' has to be relative" in result.stderr
+    assert mod_manager.helper_calls() == []